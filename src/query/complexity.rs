@@ -0,0 +1,196 @@
+//! Query depth and complexity analysis.
+//!
+//! These helpers implement the "limit query depth" / "limit query complexity" protections common
+//! in GraphQL servers, built on top of [`QueryVisitor`]: [`max_depth`] reports how deeply nested a
+//! document's selection sets are, and [`complexity`] reports a weighted cost that accounts for
+//! list-returning fields.
+//!
+//! Example:
+//!
+//! ```
+//! use graphql_parser::query::{parse_query, complexity::{max_depth, complexity, default_cost}};
+//!
+//! let doc = parse_query(r#"
+//!     query TestQuery {
+//!         users(first: 10) {
+//!             id
+//!             country {
+//!                 id
+//!             }
+//!         }
+//!     }
+//! "#).expect("Failed to parse query");
+//!
+//! assert_eq!(max_depth(&doc), 3);
+//! assert_eq!(complexity(&doc, default_cost), 31);
+//! ```
+//!
+//! [`QueryVisitor`]: ../query_visitor/trait.QueryVisitor.html
+
+use std::collections::{HashMap, HashSet};
+
+use super::ast::*;
+use super::query_visitor::{collect_fragments, walk_field, walk_selection_set, QueryVisitor};
+
+/// The paging arguments that make a field act as a list multiplier over its subtree.
+const PAGING_ARGUMENTS: &[&str] = &["first", "last", "limit"];
+
+/// The default per-field cost used by [`complexity`]: every field costs `1`.
+pub fn default_cost<'a, T: Text<'a>>(_field: &Field<'a, T>) -> u32 {
+    1
+}
+
+/// Compute the maximum selection-set nesting depth of a document.
+///
+/// Fragment spreads resolve into their `FragmentDefinition`'s selection set and count
+/// transparently, and inline fragment selection sets count at the same depth as their parent.
+/// A fragment that (directly or mutually) spreads itself does not cause infinite recursion: the
+/// cycle is simply not descended into again.
+pub fn max_depth<'a, T: Text<'a>>(doc: &'a Document<'a, T>) -> usize {
+    let mut visitor = DepthVisitor::new(doc);
+
+    for def in &doc.definitions {
+        if let Definition::Operation(op) = def {
+            super::query_visitor::walk_operation_definition(&mut visitor, op);
+        }
+    }
+
+    visitor.max_depth
+}
+
+/// Compute the complexity of a document: a weighted sum of field costs.
+///
+/// Each field contributes `cost_fn(field)` to the total, ambiently multiplied by the paging
+/// multipliers of its ancestors. A field carrying a paging argument (`first`, `last`, or `limit`)
+/// with an integer literal value multiplies the cost of everything in its subtree by that value.
+/// Fragment spreads are resolved the same way as in [`max_depth`], with the same cycle guard.
+///
+/// The running total is accumulated and saturated in `u64` internally, then clamped to
+/// [`u32::MAX`] on return, so a query with abusively large nested paging arguments is reported as
+/// maximally expensive rather than overflowing (and wrapping back around to a small number).
+pub fn complexity<'a, T, F>(doc: &'a Document<'a, T>, cost_fn: F) -> u32
+where
+    T: Text<'a>,
+    F: Fn(&Field<'a, T>) -> u32,
+{
+    let mut visitor = ComplexityVisitor::new(doc, cost_fn);
+
+    for def in &doc.definitions {
+        if let Definition::Operation(op) = def {
+            super::query_visitor::walk_operation_definition(&mut visitor, op);
+        }
+    }
+
+    visitor.total.min(u32::MAX as u64) as u32
+}
+
+fn paging_multiplier<'a, T: Text<'a>>(field: &Field<'a, T>) -> u32 {
+    for (name, value) in &field.arguments {
+        if PAGING_ARGUMENTS.contains(&name.as_ref()) {
+            if let Value::Int(number) = value {
+                if let Some(n) = number.as_i64() {
+                    return n.max(0) as u32;
+                }
+            }
+        }
+    }
+
+    1
+}
+
+struct DepthVisitor<'a, T: Text<'a>> {
+    fragments: HashMap<&'a str, &'a FragmentDefinition<'a, T>>,
+    visiting: HashSet<&'a str>,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'a, T: Text<'a>> DepthVisitor<'a, T> {
+    fn new(doc: &'a Document<'a, T>) -> Self {
+        Self {
+            fragments: collect_fragments(doc),
+            visiting: HashSet::new(),
+            depth: 0,
+            max_depth: 0,
+        }
+    }
+}
+
+impl<'a, T: Text<'a>> QueryVisitor<'a, T> for DepthVisitor<'a, T> {
+    fn visit_selection_set(&mut self, node: &'a SelectionSet<'a, T>) {
+        // A leaf field's selection set is empty and adds no nesting level.
+        if node.items.is_empty() {
+            return;
+        }
+
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        walk_selection_set(self, node);
+        self.depth -= 1;
+    }
+
+    fn visit_inline_fragment(&mut self, node: &'a InlineFragment<'a, T>) {
+        walk_selection_set(self, &node.selection_set);
+    }
+
+    fn visit_fragment_spread(&mut self, node: &'a FragmentSpread<'a, T>) {
+        let name = node.fragment_name.as_ref();
+        if !self.visiting.insert(name) {
+            return;
+        }
+
+        if let Some(fragment) = self.fragments.get(name) {
+            walk_selection_set(self, &fragment.selection_set);
+        }
+
+        self.visiting.remove(name);
+    }
+}
+
+struct ComplexityVisitor<'a, T: Text<'a>, F> {
+    fragments: HashMap<&'a str, &'a FragmentDefinition<'a, T>>,
+    visiting: HashSet<&'a str>,
+    cost_fn: F,
+    // Widened to u64 (and accumulated with saturating arithmetic) so that nested paging
+    // multipliers can't wrap a `u32` back around to a small number and defeat the complexity
+    // guard they're meant to enforce.
+    multiplier: u64,
+    total: u64,
+}
+
+impl<'a, T: Text<'a>, F: Fn(&Field<'a, T>) -> u32> ComplexityVisitor<'a, T, F> {
+    fn new(doc: &'a Document<'a, T>, cost_fn: F) -> Self {
+        Self {
+            fragments: collect_fragments(doc),
+            visiting: HashSet::new(),
+            cost_fn,
+            multiplier: 1,
+            total: 0,
+        }
+    }
+}
+
+impl<'a, T: Text<'a>, F: Fn(&Field<'a, T>) -> u32> QueryVisitor<'a, T> for ComplexityVisitor<'a, T, F> {
+    fn visit_field(&mut self, node: &'a Field<'a, T>) {
+        let cost = ((self.cost_fn)(node) as u64).saturating_mul(self.multiplier);
+        self.total = self.total.saturating_add(cost);
+
+        let previous_multiplier = self.multiplier;
+        self.multiplier = self.multiplier.saturating_mul(paging_multiplier(node) as u64);
+        walk_field(self, node);
+        self.multiplier = previous_multiplier;
+    }
+
+    fn visit_fragment_spread(&mut self, node: &'a FragmentSpread<'a, T>) {
+        let name = node.fragment_name.as_ref();
+        if !self.visiting.insert(name) {
+            return;
+        }
+
+        if let Some(fragment) = self.fragments.get(name) {
+            walk_selection_set(self, &fragment.selection_set);
+        }
+
+        self.visiting.remove(name);
+    }
+}
@@ -1,9 +1,13 @@
 //! Query syntax tree traversal.
 //!
 //! Each method of [`QueryVisitor`] is a hook that can be overridden to customize the behavior when
-//! visiting the corresponding type of node. By default, the methods don't do anything. The actual
-//! walking of the ast is done by the `walk_*` functions. So to run a visitor over the whole
-//! document you should use [`walk_document`].
+//! visiting the corresponding type of node. By default, each `visit_*` method calls its matching
+//! `walk_*` function to recurse into the node's children, so to run a visitor over a whole
+//! document you should call `visitor.visit_document(&doc)` directly (not a separate top-level
+//! driver function) -- that way an override of `visit_document` itself is honored too, same as
+//! every other hook. An override that doesn't call the matching `walk_*` function prunes that
+//! subtree from the traversal, while an override that calls it selectively (or does its own
+//! partial descent) gets full control over what gets visited next.
 //!
 //! Example:
 //!
@@ -11,7 +15,7 @@
 //! use graphql_parser::query::{
 //!     Field,
 //!     parse_query,
-//!     query_visitor::{QueryVisitor, walk_document},
+//!     query_visitor::QueryVisitor,
 //! };
 //!
 //! struct FieldsCounter {
@@ -44,167 +48,504 @@
 //!         }
 //!     "#).expect("Failed to parse query");
 //!
-//!     walk_document(&mut number_of_type, &doc);
+//!     number_of_type.visit_document(&doc);
 //!
 //!     assert_eq!(number_of_type.count, 2);
 //! }
 //! ```
 //!
 //! [`QueryVisitor`]: /graphql_parser/query/query_visitor/trait.QueryVisitor.html
-//! [`walk_document`]: /graphql_parser/query/query_visitor/fn.walk_document.html
 
 #![allow(unused_variables)]
 
+use std::collections::HashMap;
+
 use super::ast::*;
 
 /// Trait for easy query syntax tree traversal.
 ///
 /// See [module docs](/graphql_parser/query/query_visitor/index.html) for more info.
 pub trait QueryVisitor<'ast, T: Text<'ast>> {
-    fn visit_document(&mut self, node: &'ast Document<'ast, T>) {}
+    fn visit_document(&mut self, node: &'ast Document<'ast, T>) {
+        walk_document(self, node);
+    }
 
-    fn visit_definition(&mut self, node: &'ast Definition<'ast, T>) {}
+    fn visit_definition(&mut self, node: &'ast Definition<'ast, T>) {
+        walk_definition(self, node);
+    }
 
-    fn visit_fragment_definition(&mut self, node: &'ast FragmentDefinition<'ast, T>) {}
+    fn visit_fragment_definition(&mut self, node: &'ast FragmentDefinition<'ast, T>) {
+        walk_fragment_definition(self, node);
+    }
 
-    fn visit_operation_definition(&mut self, node: &'ast OperationDefinition<'ast, T>) {}
+    fn visit_operation_definition(&mut self, node: &'ast OperationDefinition<'ast, T>) {
+        walk_operation_definition(self, node);
+    }
 
-    fn visit_query(&mut self, node: &'ast Query<'ast, T>) {}
+    fn visit_query(&mut self, node: &'ast Query<'ast, T>) {
+        walk_query(self, node);
+    }
 
-    fn visit_mutation(&mut self, node: &'ast Mutation<'ast, T>) {}
+    fn visit_mutation(&mut self, node: &'ast Mutation<'ast, T>) {
+        walk_mutation(self, node);
+    }
 
-    fn visit_subscription(&mut self, node: &'ast Subscription<'ast, T>) {}
+    fn visit_subscription(&mut self, node: &'ast Subscription<'ast, T>) {
+        walk_subscription(self, node);
+    }
 
-    fn visit_selection_set(&mut self, node: &'ast SelectionSet<'ast, T>) {}
+    fn visit_selection_set(&mut self, node: &'ast SelectionSet<'ast, T>) {
+        walk_selection_set(self, node);
+    }
 
-    fn visit_variable_definition(&mut self, node: &'ast VariableDefinition<'ast, T>) {}
+    fn visit_variable_definition(&mut self, node: &'ast VariableDefinition<'ast, T>) {
+        walk_variable_definition(self, node);
+    }
 
-    fn visit_selection(&mut self, node: &'ast Selection<'ast, T>) {}
+    fn visit_type(&mut self, node: &'ast Type<'ast, T>) {
+        walk_type(self, node);
+    }
 
-    fn visit_field(&mut self, node: &'ast Field<'ast, T>) {}
+    fn visit_selection(&mut self, node: &'ast Selection<'ast, T>) {
+        walk_selection(self, node);
+    }
 
-    fn visit_fragment_spread(&mut self, node: &'ast FragmentSpread<'ast, T>) {}
+    fn visit_field(&mut self, node: &'ast Field<'ast, T>) {
+        walk_field(self, node);
+    }
+
+    fn visit_fragment_spread(&mut self, node: &'ast FragmentSpread<'ast, T>) {
+        walk_fragment_spread(self, node);
+    }
+
+    fn visit_inline_fragment(&mut self, node: &'ast InlineFragment<'ast, T>) {
+        walk_inline_fragment(self, node);
+    }
+
+    fn visit_argument(&mut self, node: &'ast (T::Value, Value<'ast, T>)) {
+        walk_argument(self, node);
+    }
 
-    fn visit_inline_fragment(&mut self, node: &'ast InlineFragment<'ast, T>) {}
+    fn visit_directive(&mut self, node: &'ast Directive<'ast, T>) {
+        walk_directive(self, node);
+    }
+
+    fn visit_value(&mut self, node: &'ast Value<'ast, T>) {
+        walk_value(self, node);
+    }
 }
 
 
-/// Walk a query syntax tree and call the visitor methods for each type of node.
+/// Walk the children of a [`Document`]: its definitions.
 ///
-/// This function is how you should initiate a visitor.
+/// This is the default body of [`QueryVisitor::visit_document`]; to run a visitor over a whole
+/// document, call `visitor.visit_document(&doc)` rather than this function directly, so that an
+/// override of `visit_document` itself still takes effect.
 pub fn walk_document<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Document<'ast, T>) {
-    visitor.visit_document(node);
     for def in &node.definitions {
-        walk_definition(visitor, def);
+        visitor.visit_definition(def);
     }
 }
 
-fn walk_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Definition<'ast, T>) {
+/// Walk the children of a [`Definition`], dispatching to the matching operation or fragment.
+pub fn walk_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Definition<'ast, T>) {
     use super::ast::Definition::*;
 
-    visitor.visit_definition(node);
     match node {
         Operation(inner) => {
-            walk_operation_definition(visitor, inner);
+            visitor.visit_operation_definition(inner);
         },
         Fragment(inner) => {
-            walk_fragment_definition(visitor, inner);
+            visitor.visit_fragment_definition(inner);
         },
     }
 }
 
-fn walk_fragment_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast FragmentDefinition<'ast, T>) {
-    walk_selection_set(visitor, &node.selection_set);
+/// Walk the children of a [`FragmentDefinition`].
+pub fn walk_fragment_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast FragmentDefinition<'ast, T>) {
+    visitor.visit_selection_set(&node.selection_set);
 }
 
-fn walk_operation_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast OperationDefinition<'ast, T>) {
+/// Walk the children of an [`OperationDefinition`], dispatching to the matching operation kind.
+pub fn walk_operation_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast OperationDefinition<'ast, T>) {
     use super::ast::OperationDefinition::*;
 
-    visitor.visit_operation_definition(node);
     match node {
         SelectionSet(inner) => {
-            walk_selection_set(visitor, inner);
+            visitor.visit_selection_set(inner);
         }
         Query(inner) => {
-            walk_query(visitor, inner);
+            visitor.visit_query(inner);
         }
         Mutation(inner) => {
-            walk_mutation(visitor, inner);
+            visitor.visit_mutation(inner);
         }
         Subscription(inner) => {
-            walk_subscription(visitor, inner);
+            visitor.visit_subscription(inner);
         }
     }
 }
 
-fn walk_query<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Query<'ast, T>) {
-    visitor.visit_query(node);
-
+/// Walk the children of a [`Query`].
+pub fn walk_query<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Query<'ast, T>) {
     for var_def in &node.variable_definitions {
-        walk_variable_definition(visitor, var_def);
+        visitor.visit_variable_definition(var_def);
     }
 
-    walk_selection_set(visitor, &node.selection_set);
+    visitor.visit_selection_set(&node.selection_set);
 }
 
-fn walk_mutation<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Mutation<'ast, T>) {
-    visitor.visit_mutation(node);
-
+/// Walk the children of a [`Mutation`].
+pub fn walk_mutation<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Mutation<'ast, T>) {
     for var_def in &node.variable_definitions {
-        walk_variable_definition(visitor, var_def);
+        visitor.visit_variable_definition(var_def);
     }
 
-    walk_selection_set(visitor, &node.selection_set);
+    visitor.visit_selection_set(&node.selection_set);
 }
 
-fn walk_subscription<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Subscription<'ast, T>) {
-    visitor.visit_subscription(node);
-
+/// Walk the children of a [`Subscription`].
+pub fn walk_subscription<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Subscription<'ast, T>) {
     for var_def in &node.variable_definitions {
-        walk_variable_definition(visitor, var_def);
+        visitor.visit_variable_definition(var_def);
     }
 
-    walk_selection_set(visitor, &node.selection_set);
+    visitor.visit_selection_set(&node.selection_set);
 }
 
-fn walk_selection_set<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast SelectionSet<'ast, T>) {
-    visitor.visit_selection_set(node);
-
+/// Walk the children of a [`SelectionSet`].
+pub fn walk_selection_set<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast SelectionSet<'ast, T>) {
     for selection in &node.items {
-        walk_selection(visitor, selection);
+        visitor.visit_selection(selection);
+    }
+}
+
+/// Walk the children of a [`VariableDefinition`]: its declared type and default value, if any.
+pub fn walk_variable_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast VariableDefinition<'ast, T>) {
+    visitor.visit_type(&node.var_type);
+
+    if let Some(default_value) = &node.default_value {
+        visitor.visit_value(default_value);
     }
 }
 
-fn walk_variable_definition<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast VariableDefinition<'ast, T>) {
-    visitor.visit_variable_definition(node)
+/// Walk the children of a [`Type`], recursing into the wrapped type for lists and non-nulls.
+pub fn walk_type<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Type<'ast, T>) {
+    use super::ast::Type::*;
+
+    match node {
+        NamedType(_) => {}
+        ListType(inner) => {
+            visitor.visit_type(inner);
+        }
+        NonNullType(inner) => {
+            visitor.visit_type(inner);
+        }
+    }
 }
 
-fn walk_selection<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Selection<'ast, T>) {
+/// Walk the children of a [`Selection`], dispatching to the matching selection kind.
+pub fn walk_selection<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Selection<'ast, T>) {
     use super::ast::Selection::*;
 
-    visitor.visit_selection(node);
     match node {
         Field(inner) => {
-            walk_field(visitor, inner);
+            visitor.visit_field(inner);
         }
         FragmentSpread(inner) => {
-            walk_fragment_spread(visitor, inner);
+            visitor.visit_fragment_spread(inner);
         }
         InlineFragment(inner) => {
-            walk_inline_fragment(visitor, inner);
+            visitor.visit_inline_fragment(inner);
         }
     }
 }
 
-fn walk_field<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Field<'ast, T>) {
-    visitor.visit_field(node)
+/// Walk the children of a [`Field`]: its arguments, directives, and selection set.
+pub fn walk_field<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Field<'ast, T>) {
+    for argument in &node.arguments {
+        visitor.visit_argument(argument);
+    }
+
+    for directive in &node.directives {
+        visitor.visit_directive(directive);
+    }
+
+    visitor.visit_selection_set(&node.selection_set);
+}
+
+/// Walk the children of a [`FragmentSpread`]: its directives.
+pub fn walk_fragment_spread<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast FragmentSpread<'ast, T>) {
+    for directive in &node.directives {
+        visitor.visit_directive(directive);
+    }
+}
+
+/// Walk the children of an [`InlineFragment`]: its directives and selection set.
+pub fn walk_inline_fragment<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast InlineFragment<'ast, T>) {
+    for directive in &node.directives {
+        visitor.visit_directive(directive);
+    }
+
+    visitor.visit_selection_set(&node.selection_set);
+}
+
+/// Walk the children of an argument (a name/value pair): just its value.
+pub fn walk_argument<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast (T::Value, Value<'ast, T>)) {
+    visitor.visit_value(&node.1);
+}
+
+/// Walk the children of a [`Directive`]: its arguments.
+pub fn walk_directive<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Directive<'ast, T>) {
+    for argument in &node.arguments {
+        visitor.visit_argument(argument);
+    }
+}
+
+/// Walk the children of a [`Value`], recursing into list items and object field values.
+pub fn walk_value<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast Value<'ast, T>) {
+    use super::ast::Value::*;
+
+    match node {
+        List(items) => {
+            for item in items {
+                visitor.visit_value(item);
+            }
+        }
+        Object(fields) => {
+            for value in fields.values() {
+                visitor.visit_value(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect a document's top-level fragment definitions keyed by name.
+///
+/// Shared by analyses built on [`QueryVisitor`] (such as `complexity` and `signature`) that need
+/// to resolve a [`FragmentSpread`] back to its [`FragmentDefinition`] while walking.
+pub(crate) fn collect_fragments<'ast, T: Text<'ast>>(doc: &'ast Document<'ast, T>) -> HashMap<&'ast str, &'ast FragmentDefinition<'ast, T>> {
+    use super::ast::Definition::*;
+
+    doc.definitions
+        .iter()
+        .filter_map(|def| match def {
+            Fragment(fragment) => Some((fragment.name.as_ref(), fragment)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Trait for rewriting a query syntax tree, taking and returning owned nodes.
+///
+/// Mirrors [`QueryVisitor`], but where `visit_*` observes borrowed nodes, `fold_*` consumes a
+/// node and returns its (possibly rewritten) replacement. The default implementation of each
+/// `fold_*` method recurses into the node's children and reconstructs the node unchanged, the
+/// same way the matching `walk_*` function does for [`QueryVisitor`]; overriding a method lets
+/// you rewrite, drop, or replace a node before or after that reconstruction. `fold_selection`
+/// returns `Option` so a fold can drop a selection from its parent selection set entirely, which
+/// is how passes like dead-branch elimination (e.g. stripping `@skip(if: true)` fields) work.
+pub trait QueryFold<'ast, T: Text<'ast>> {
+    fn fold_document(&mut self, node: Document<'ast, T>) -> Document<'ast, T> {
+        fold_document(self, node)
+    }
+
+    fn fold_definition(&mut self, node: Definition<'ast, T>) -> Definition<'ast, T> {
+        fold_definition(self, node)
+    }
+
+    fn fold_fragment_definition(&mut self, node: FragmentDefinition<'ast, T>) -> FragmentDefinition<'ast, T> {
+        fold_fragment_definition(self, node)
+    }
+
+    fn fold_operation_definition(&mut self, node: OperationDefinition<'ast, T>) -> OperationDefinition<'ast, T> {
+        fold_operation_definition(self, node)
+    }
+
+    fn fold_query(&mut self, node: Query<'ast, T>) -> Query<'ast, T> {
+        fold_query(self, node)
+    }
+
+    fn fold_mutation(&mut self, node: Mutation<'ast, T>) -> Mutation<'ast, T> {
+        fold_mutation(self, node)
+    }
+
+    fn fold_subscription(&mut self, node: Subscription<'ast, T>) -> Subscription<'ast, T> {
+        fold_subscription(self, node)
+    }
+
+    fn fold_selection_set(&mut self, node: SelectionSet<'ast, T>) -> SelectionSet<'ast, T> {
+        fold_selection_set(self, node)
+    }
+
+    fn fold_variable_definition(&mut self, node: VariableDefinition<'ast, T>) -> VariableDefinition<'ast, T> {
+        fold_variable_definition(self, node)
+    }
+
+    fn fold_type(&mut self, node: Type<'ast, T>) -> Type<'ast, T> {
+        fold_type(self, node)
+    }
+
+    fn fold_selection(&mut self, node: Selection<'ast, T>) -> Option<Selection<'ast, T>> {
+        fold_selection(self, node)
+    }
+
+    fn fold_field(&mut self, node: Field<'ast, T>) -> Field<'ast, T> {
+        fold_field(self, node)
+    }
+
+    fn fold_fragment_spread(&mut self, node: FragmentSpread<'ast, T>) -> FragmentSpread<'ast, T> {
+        fold_fragment_spread(self, node)
+    }
+
+    fn fold_inline_fragment(&mut self, node: InlineFragment<'ast, T>) -> InlineFragment<'ast, T> {
+        fold_inline_fragment(self, node)
+    }
+
+    fn fold_argument(&mut self, node: (T::Value, Value<'ast, T>)) -> (T::Value, Value<'ast, T>) {
+        fold_argument(self, node)
+    }
+
+    fn fold_directive(&mut self, node: Directive<'ast, T>) -> Directive<'ast, T> {
+        fold_directive(self, node)
+    }
+
+    fn fold_value(&mut self, node: Value<'ast, T>) -> Value<'ast, T> {
+        fold_value(self, node)
+    }
+}
+
+/// Fold the children of a [`Document`].
+pub fn fold_document<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: Document<'ast, T>) -> Document<'ast, T> {
+    node.definitions = node.definitions.into_iter().map(|def| fold.fold_definition(def)).collect();
+    node
 }
 
-fn walk_fragment_spread<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast FragmentSpread<'ast, T>) {
-    visitor.visit_fragment_spread(node)
+/// Fold the children of a [`Definition`], dispatching to the matching operation or fragment.
+pub fn fold_definition<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, node: Definition<'ast, T>) -> Definition<'ast, T> {
+    use super::ast::Definition::*;
+
+    match node {
+        Operation(inner) => Operation(fold.fold_operation_definition(inner)),
+        Fragment(inner) => Fragment(fold.fold_fragment_definition(inner)),
+    }
 }
 
-fn walk_inline_fragment<'ast, T: Text<'ast>, V: QueryVisitor<'ast, T>>(visitor: &mut V, node: &'ast InlineFragment<'ast, T>) {
-    visitor.visit_inline_fragment(node);
-    walk_selection_set(visitor, &node.selection_set);
-}
\ No newline at end of file
+/// Fold the children of a [`FragmentDefinition`].
+pub fn fold_fragment_definition<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: FragmentDefinition<'ast, T>) -> FragmentDefinition<'ast, T> {
+    node.selection_set = fold.fold_selection_set(node.selection_set);
+    node
+}
+
+/// Fold the children of an [`OperationDefinition`], dispatching to the matching operation kind.
+pub fn fold_operation_definition<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, node: OperationDefinition<'ast, T>) -> OperationDefinition<'ast, T> {
+    use super::ast::OperationDefinition::*;
+
+    match node {
+        SelectionSet(inner) => SelectionSet(fold.fold_selection_set(inner)),
+        Query(inner) => Query(fold.fold_query(inner)),
+        Mutation(inner) => Mutation(fold.fold_mutation(inner)),
+        Subscription(inner) => Subscription(fold.fold_subscription(inner)),
+    }
+}
+
+/// Fold the children of a [`Query`].
+pub fn fold_query<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: Query<'ast, T>) -> Query<'ast, T> {
+    node.variable_definitions = node.variable_definitions.into_iter().map(|var_def| fold.fold_variable_definition(var_def)).collect();
+    node.selection_set = fold.fold_selection_set(node.selection_set);
+    node
+}
+
+/// Fold the children of a [`Mutation`].
+pub fn fold_mutation<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: Mutation<'ast, T>) -> Mutation<'ast, T> {
+    node.variable_definitions = node.variable_definitions.into_iter().map(|var_def| fold.fold_variable_definition(var_def)).collect();
+    node.selection_set = fold.fold_selection_set(node.selection_set);
+    node
+}
+
+/// Fold the children of a [`Subscription`].
+pub fn fold_subscription<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: Subscription<'ast, T>) -> Subscription<'ast, T> {
+    node.variable_definitions = node.variable_definitions.into_iter().map(|var_def| fold.fold_variable_definition(var_def)).collect();
+    node.selection_set = fold.fold_selection_set(node.selection_set);
+    node
+}
+
+/// Fold the children of a [`SelectionSet`], dropping any selection whose fold returns `None`.
+pub fn fold_selection_set<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: SelectionSet<'ast, T>) -> SelectionSet<'ast, T> {
+    node.items = node.items.into_iter().filter_map(|selection| fold.fold_selection(selection)).collect();
+    node
+}
+
+/// Fold the children of a [`VariableDefinition`]: its declared type and default value, if any.
+pub fn fold_variable_definition<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: VariableDefinition<'ast, T>) -> VariableDefinition<'ast, T> {
+    node.var_type = fold.fold_type(node.var_type);
+    node.default_value = node.default_value.map(|value| fold.fold_value(value));
+    node
+}
+
+/// Fold the children of a [`Type`], recursing into the wrapped type for lists and non-nulls.
+pub fn fold_type<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, node: Type<'ast, T>) -> Type<'ast, T> {
+    use super::ast::Type::*;
+
+    match node {
+        NamedType(name) => NamedType(name),
+        ListType(inner) => ListType(Box::new(fold.fold_type(*inner))),
+        NonNullType(inner) => NonNullType(Box::new(fold.fold_type(*inner))),
+    }
+}
+
+/// Fold a [`Selection`], dispatching to the matching selection kind.
+pub fn fold_selection<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, node: Selection<'ast, T>) -> Option<Selection<'ast, T>> {
+    use super::ast::Selection::*;
+
+    Some(match node {
+        Field(inner) => Field(fold.fold_field(inner)),
+        FragmentSpread(inner) => FragmentSpread(fold.fold_fragment_spread(inner)),
+        InlineFragment(inner) => InlineFragment(fold.fold_inline_fragment(inner)),
+    })
+}
+
+/// Fold the children of a [`Field`]: its arguments, directives, and selection set.
+pub fn fold_field<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: Field<'ast, T>) -> Field<'ast, T> {
+    node.arguments = node.arguments.into_iter().map(|argument| fold.fold_argument(argument)).collect();
+    node.directives = node.directives.into_iter().map(|directive| fold.fold_directive(directive)).collect();
+    node.selection_set = fold.fold_selection_set(node.selection_set);
+    node
+}
+
+/// Fold the children of a [`FragmentSpread`]: its directives.
+pub fn fold_fragment_spread<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: FragmentSpread<'ast, T>) -> FragmentSpread<'ast, T> {
+    node.directives = node.directives.into_iter().map(|directive| fold.fold_directive(directive)).collect();
+    node
+}
+
+/// Fold the children of an [`InlineFragment`]: its directives and selection set.
+pub fn fold_inline_fragment<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: InlineFragment<'ast, T>) -> InlineFragment<'ast, T> {
+    node.directives = node.directives.into_iter().map(|directive| fold.fold_directive(directive)).collect();
+    node.selection_set = fold.fold_selection_set(node.selection_set);
+    node
+}
+
+/// Fold the children of an argument (a name/value pair): just its value.
+pub fn fold_argument<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, node: (T::Value, Value<'ast, T>)) -> (T::Value, Value<'ast, T>) {
+    let (name, value) = node;
+    (name, fold.fold_value(value))
+}
+
+/// Fold the children of a [`Directive`]: its arguments.
+pub fn fold_directive<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, mut node: Directive<'ast, T>) -> Directive<'ast, T> {
+    node.arguments = node.arguments.into_iter().map(|argument| fold.fold_argument(argument)).collect();
+    node
+}
+
+/// Fold the children of a [`Value`], recursing into list items and object field values.
+pub fn fold_value<'ast, T: Text<'ast>, F: QueryFold<'ast, T>>(fold: &mut F, node: Value<'ast, T>) -> Value<'ast, T> {
+    use super::ast::Value::*;
+
+    match node {
+        List(items) => List(items.into_iter().map(|item| fold.fold_value(item)).collect()),
+        Object(fields) => Object(fields.into_iter().map(|(key, value)| (key, fold.fold_value(value))).collect()),
+        other => other,
+    }
+}
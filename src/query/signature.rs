@@ -0,0 +1,331 @@
+//! Stable query fingerprints.
+//!
+//! [`signature`] walks a [`Document`], normalizes away insignificant formatting and ordering, and
+//! hashes the result into a stable fingerprint usable as a cache key or for grouping telemetry:
+//! fields within a selection set are sorted by response key, arguments are sorted by name,
+//! fragment spreads are inlined (guarding against cycles), and by default scalar argument
+//! literals are replaced with a placeholder so only the query shape, not its concrete values, is
+//! fingerprinted.
+//!
+//! Example:
+//!
+//! ```
+//! use graphql_parser::query::{parse_query, signature::signature};
+//!
+//! let direct = parse_query(r#"
+//!     query { b a(y: 2, x: 1) }
+//! "#).expect("Failed to parse query");
+//!
+//! let reordered_and_extracted = parse_query(r#"
+//!     query {
+//!         a(x: 1, y: 2)
+//!         ...Rest
+//!     }
+//!     fragment Rest on Query {
+//!         b
+//!     }
+//! "#).expect("Failed to parse query");
+//!
+//! // Reordering fields, reordering arguments, and extracting fields into a fragment are all
+//! // insignificant to the query's shape, so they all produce the same signature.
+//! assert_eq!(signature(&direct).normalized_query, signature(&reordered_and_extracted).normalized_query);
+//! assert_eq!(signature(&direct).hash, signature(&reordered_and_extracted).hash);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use sha2::{Digest, Sha256};
+
+use super::ast::*;
+use super::query_visitor::{collect_fragments, QueryVisitor};
+
+/// The result of fingerprinting a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// The hex-encoded digest of the normalized query.
+    pub hash: String,
+    /// The normalized query text that was fed into the digest.
+    pub normalized_query: String,
+}
+
+/// Options controlling how a document is normalized before hashing.
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureOptions {
+    /// Replace scalar argument literals (ints, floats, strings, booleans, enums, null) with a
+    /// placeholder so only the query structure, not its concrete values, is fingerprinted.
+    /// Defaults to `true`.
+    pub mask_values: bool,
+}
+
+impl Default for SignatureOptions {
+    fn default() -> Self {
+        Self { mask_values: true }
+    }
+}
+
+/// Compute a stable fingerprint of a document using the default options and a SHA-256 digest.
+pub fn signature<'a, T: Text<'a>>(doc: &'a Document<'a, T>) -> Signature {
+    signature_with::<T, Sha256>(doc, SignatureOptions::default())
+}
+
+/// Compute a stable fingerprint of a document with a specific digest algorithm and options.
+pub fn signature_with<'a, T: Text<'a>, D: Digest>(doc: &'a Document<'a, T>, options: SignatureOptions) -> Signature {
+    let normalized_query = normalize(doc, options);
+
+    let mut hasher = D::new();
+    hasher.update(normalized_query.as_bytes());
+    let hash = hex_encode(&hasher.finalize());
+
+    Signature { hash, normalized_query }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(out, "{:02x}", byte).expect("writing to a String never fails");
+    }
+    out
+}
+
+fn normalize<'a, T: Text<'a>>(doc: &'a Document<'a, T>, options: SignatureOptions) -> String {
+    let mut normalizer = Normalizer::new(doc, options);
+
+    for def in &doc.definitions {
+        if let Definition::Operation(op) = def {
+            normalizer.write_operation(op);
+        }
+    }
+
+    normalizer.buf
+}
+
+struct Normalizer<'a, T: Text<'a>> {
+    fragments: HashMap<&'a str, &'a FragmentDefinition<'a, T>>,
+    visiting: HashSet<&'a str>,
+    options: SignatureOptions,
+    buf: String,
+}
+
+impl<'a, T: Text<'a>> Normalizer<'a, T> {
+    fn new(doc: &'a Document<'a, T>, options: SignatureOptions) -> Self {
+        Self {
+            fragments: collect_fragments(doc),
+            visiting: HashSet::new(),
+            options,
+            buf: String::new(),
+        }
+    }
+
+    fn write_operation(&mut self, op: &'a OperationDefinition<'a, T>) {
+        use super::ast::OperationDefinition::*;
+
+        match op {
+            SelectionSet(set) => {
+                self.buf.push_str("query");
+                self.visit_selection_set(set);
+            }
+            Query(query) => {
+                self.buf.push_str("query");
+                self.visit_selection_set(&query.selection_set);
+            }
+            Mutation(mutation) => {
+                self.buf.push_str("mutation");
+                self.visit_selection_set(&mutation.selection_set);
+            }
+            Subscription(subscription) => {
+                self.buf.push_str("subscription");
+                self.visit_selection_set(&subscription.selection_set);
+            }
+        }
+    }
+
+    fn response_key(&self, selection: &'a Selection<'a, T>) -> String {
+        match selection {
+            Selection::Field(field) => field
+                .alias
+                .as_ref()
+                .map(|alias| alias.as_ref().to_string())
+                .unwrap_or_else(|| field.name.as_ref().to_string()),
+            Selection::FragmentSpread(spread) => format!("...{}", spread.fragment_name.as_ref()),
+            Selection::InlineFragment(_) => "...".to_string(),
+        }
+    }
+
+    fn write_value(&mut self, value: &Value<'a, T>) {
+        use super::ast::Value::*;
+
+        match value {
+            Variable(name) => {
+                write!(self.buf, "${}", name.as_ref()).unwrap();
+            }
+            List(items) => {
+                self.buf.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.buf.push(',');
+                    }
+                    self.write_value(item);
+                }
+                self.buf.push(']');
+            }
+            Object(fields) => {
+                let mut names: Vec<&T::Value> = fields.keys().collect();
+                names.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+                self.buf.push('{');
+                for (i, name) in names.iter().enumerate() {
+                    if i > 0 {
+                        self.buf.push(',');
+                    }
+                    write!(self.buf, "{}:", name.as_ref()).unwrap();
+                    self.write_value(&fields[*name]);
+                }
+                self.buf.push('}');
+            }
+            Null | Int(_) | Float(_) | String(_) | Boolean(_) | Enum(_) if self.options.mask_values => {
+                self.buf.push('?');
+            }
+            Null => self.buf.push_str("null"),
+            Int(number) => {
+                write!(self.buf, "{}", number.as_i64().unwrap_or_default()).unwrap();
+            }
+            Float(number) => {
+                write!(self.buf, "{}", number).unwrap();
+            }
+            String(string) => {
+                write!(self.buf, "{:?}", string).unwrap();
+            }
+            Boolean(value) => {
+                write!(self.buf, "{}", value).unwrap();
+            }
+            Enum(name) => self.buf.push_str(name.as_ref()),
+        }
+    }
+
+    fn write_directives(&mut self, directives: &'a [Directive<'a, T>]) {
+        if directives.is_empty() {
+            return;
+        }
+
+        let mut directives: Vec<&'a Directive<'a, T>> = directives.iter().collect();
+        directives.sort_by(|a, b| a.name.as_ref().cmp(b.name.as_ref()));
+
+        for directive in directives {
+            write!(self.buf, "@{}", directive.name.as_ref()).unwrap();
+
+            if !directive.arguments.is_empty() {
+                let mut arguments: Vec<&(T::Value, Value<'a, T>)> = directive.arguments.iter().collect();
+                arguments.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
+
+                self.buf.push('(');
+                for (i, (name, value)) in arguments.into_iter().enumerate() {
+                    if i > 0 {
+                        self.buf.push(',');
+                    }
+                    write!(self.buf, "{}:", name.as_ref()).unwrap();
+                    self.write_value(value);
+                }
+                self.buf.push(')');
+            }
+        }
+    }
+
+    /// Splice selections into `out`, resolving (directive-free) fragment spreads into their
+    /// constituent selections instead of leaving them as a nested node. This is what makes `{ a
+    /// ...F }` (with `fragment F on T { b }`) normalize identically to `{ a b }`: the fragment's
+    /// fields become ordinary siblings that participate in the parent's by-response-key sort,
+    /// rather than being wrapped in their own brace pair. A spread that carries directives is
+    /// left as-is (handled by `visit_fragment_spread`), since `@skip`/`@include` make its fields
+    /// conditional on something the sibling fields aren't.
+    fn flatten_selections(&mut self, items: &'a [Selection<'a, T>], out: &mut Vec<&'a Selection<'a, T>>) {
+        for selection in items {
+            match selection {
+                Selection::FragmentSpread(spread) if spread.directives.is_empty() => {
+                    let name = spread.fragment_name.as_ref();
+                    if !self.visiting.insert(name) {
+                        continue;
+                    }
+
+                    if let Some(fragment) = self.fragments.get(name) {
+                        self.flatten_selections(&fragment.selection_set.items, out);
+                    }
+
+                    self.visiting.remove(name);
+                }
+                other => out.push(other),
+            }
+        }
+    }
+}
+
+impl<'a, T: Text<'a>> QueryVisitor<'a, T> for Normalizer<'a, T> {
+    fn visit_selection_set(&mut self, node: &'a SelectionSet<'a, T>) {
+        let mut items: Vec<&'a Selection<'a, T>> = Vec::new();
+        self.flatten_selections(&node.items, &mut items);
+        items.sort_by_key(|selection| self.response_key(selection));
+
+        self.buf.push('{');
+        for (i, selection) in items.into_iter().enumerate() {
+            if i > 0 {
+                self.buf.push(' ');
+            }
+            self.visit_selection(selection);
+        }
+        self.buf.push('}');
+    }
+
+    fn visit_field(&mut self, node: &'a Field<'a, T>) {
+        if let Some(alias) = &node.alias {
+            write!(self.buf, "{}:", alias.as_ref()).unwrap();
+        }
+        self.buf.push_str(node.name.as_ref());
+
+        if !node.arguments.is_empty() {
+            let mut arguments: Vec<&(T::Value, Value<'a, T>)> = node.arguments.iter().collect();
+            arguments.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
+
+            self.buf.push('(');
+            for (i, (name, value)) in arguments.into_iter().enumerate() {
+                if i > 0 {
+                    self.buf.push(',');
+                }
+                write!(self.buf, "{}:", name.as_ref()).unwrap();
+                self.write_value(value);
+            }
+            self.buf.push(')');
+        }
+
+        self.write_directives(&node.directives);
+
+        if !node.selection_set.items.is_empty() {
+            self.visit_selection_set(&node.selection_set);
+        }
+    }
+
+    fn visit_fragment_spread(&mut self, node: &'a FragmentSpread<'a, T>) {
+        self.write_directives(&node.directives);
+
+        let name = node.fragment_name.as_ref();
+        if !self.visiting.insert(name) {
+            return;
+        }
+
+        if let Some(fragment) = self.fragments.get(name) {
+            self.visit_selection_set(&fragment.selection_set);
+        }
+
+        self.visiting.remove(name);
+    }
+
+    fn visit_inline_fragment(&mut self, node: &'a InlineFragment<'a, T>) {
+        self.buf.push_str("...");
+
+        if let Some(TypeCondition::On(name)) = &node.type_condition {
+            write!(self.buf, "on {}", name.as_ref()).unwrap();
+        }
+
+        self.write_directives(&node.directives);
+        self.visit_selection_set(&node.selection_set);
+    }
+}